@@ -0,0 +1,67 @@
+/// Computes the inclusive `(start, end)` line-index windows to print around
+/// each match in `indices`: `before` lines before, `after` lines after,
+/// clamped to `[0, total_lines)` and merged when adjacent or overlapping.
+pub fn context_windows(
+    indices: &[usize],
+    before: usize,
+    after: usize,
+    total_lines: usize,
+) -> Vec<(usize, usize)> {
+    if total_lines == 0 || indices.is_empty() {
+        return Vec::new();
+    }
+
+    let last_line = total_lines - 1;
+    let mut windows: Vec<(usize, usize)> = indices
+        .iter()
+        .map(|&i| (i.saturating_sub(before), (i + after).min(last_line)))
+        .collect();
+    windows.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in windows.drain(..) {
+        match merged.last_mut() {
+            // Adjacent or overlapping windows merge into one group, matching
+            // grep's behavior of not repeating the `--` separator for them.
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_match_window() {
+        assert_eq!(context_windows(&[5], 1, 2, 10), vec![(4, 7)]);
+    }
+
+    #[test]
+    fn clamps_at_file_start() {
+        assert_eq!(context_windows(&[0], 3, 0, 10), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn clamps_at_file_end() {
+        assert_eq!(context_windows(&[9], 0, 3, 10), vec![(9, 9)]);
+    }
+
+    #[test]
+    fn merges_overlapping_windows() {
+        assert_eq!(context_windows(&[2, 4], 1, 1, 10), vec![(1, 5)]);
+    }
+
+    #[test]
+    fn merges_adjacent_windows() {
+        assert_eq!(context_windows(&[2, 4], 0, 1, 10), vec![(2, 5)]);
+    }
+
+    #[test]
+    fn keeps_separate_windows_apart() {
+        assert_eq!(context_windows(&[2, 20], 1, 1, 30), vec![(1, 3), (19, 21)]);
+    }
+}
@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expands `path` into a flat list of files to search: a plain file passes
+/// through unchanged, a directory is walked recursively. Unreadable
+/// directories are logged and skipped rather than aborting the whole run.
+pub fn collect_files(path: &str) -> Vec<PathBuf> {
+    let path = Path::new(path);
+
+    if !path.is_dir() {
+        return vec![path.to_path_buf()];
+    }
+
+    let mut files = Vec::new();
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("minigrep: cannot read directory '{}': {}", path.display(), e);
+            return files;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            files.extend(collect_files(&entry_path.to_string_lossy()));
+        } else {
+            files.push(entry_path);
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("minigrep-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("top.txt"), "top").unwrap();
+        fs::write(dir.join("nested").join("deep.txt"), "deep").unwrap();
+        dir
+    }
+
+    #[test]
+    fn collect_files_single_file_passes_through() {
+        let dir = temp_dir("single-file");
+        let file = dir.join("top.txt");
+
+        let files = collect_files(&file.to_string_lossy());
+
+        assert_eq!(files, vec![file]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn collect_files_walks_directories_recursively() {
+        let dir = temp_dir("recursive");
+
+        let mut files = collect_files(&dir.to_string_lossy());
+        files.sort();
+
+        let mut expected = vec![dir.join("top.txt"), dir.join("nested").join("deep.txt")];
+        expected.sort();
+
+        assert_eq!(files, expected);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::IsTerminal;
+
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// When to colorize matches: always, never, or only when stdout is a TTY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn parse(value: &str) -> Option<ColorMode> {
+        match value {
+            "auto" => Some(ColorMode::Auto),
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
+            _ => None,
+        }
+    }
+
+    fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => std::io::stdout().is_terminal(),
+        }
+    }
+}
+
+/// Wraps each `(start, end)` byte range of `line` in `matches` with the
+/// highlight escape codes, leaving the rest of the line untouched.
+pub fn highlight(line: &str, matches: &[(usize, usize)], mode: ColorMode) -> String {
+    if matches.is_empty() || !mode.enabled() {
+        return line.to_string();
+    }
+
+    let mut result = String::with_capacity(line.len());
+    let mut last_end = 0;
+
+    for &(start, end) in matches {
+        result.push_str(&line[last_end..start]);
+        result.push_str(RED);
+        result.push_str(&line[start..end]);
+        result.push_str(RESET);
+        last_end = end;
+    }
+    result.push_str(&line[last_end..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_a_single_match() {
+        let highlighted = highlight("safe, fast, productive.", &[(15, 19)], ColorMode::Always);
+        assert_eq!(highlighted, "safe, fast, pro\x1b[31mduct\x1b[0mive.");
+    }
+
+    #[test]
+    fn highlights_multiple_matches() {
+        let highlighted = highlight("aXbXc", &[(1, 2), (3, 4)], ColorMode::Always);
+        assert_eq!(highlighted, "a\x1b[31mX\x1b[0mb\x1b[31mX\x1b[0mc");
+    }
+
+    #[test]
+    fn never_mode_returns_line_unchanged() {
+        let highlighted = highlight("safe, fast, productive.", &[(12, 16)], ColorMode::Never);
+        assert_eq!(highlighted, "safe, fast, productive.");
+    }
+
+    #[test]
+    fn no_matches_returns_line_unchanged() {
+        let highlighted = highlight("safe, fast, productive.", &[], ColorMode::Always);
+        assert_eq!(highlighted, "safe, fast, productive.");
+    }
+
+    #[test]
+    fn parses_color_mode_values() {
+        assert_eq!(ColorMode::parse("auto"), Some(ColorMode::Auto));
+        assert_eq!(ColorMode::parse("always"), Some(ColorMode::Always));
+        assert_eq!(ColorMode::parse("never"), Some(ColorMode::Never));
+        assert_eq!(ColorMode::parse("bogus"), None);
+    }
+}
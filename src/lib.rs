@@ -1,66 +1,245 @@
+use std::collections::HashSet;
 use std::env;
 use std::error::Error;
+use std::fmt::Write as _;
 use std::fs;
 
+mod context;
+mod files;
+mod highlight;
+mod matcher;
+
+use context::context_windows;
+use files::collect_files;
+use highlight::ColorMode;
+use matcher::{CaseInsensitiveMatcher, LiteralMatcher, Matcher, RegexMatcher};
+
+/// Consumes and parses the line count that must follow `-A`/`-B`/`-C`,
+/// without swallowing the next flag if the count is missing. A following
+/// token that does not start with '-' is always taken as the count.
+fn parse_context_count<I>(args: &mut std::iter::Peekable<I>) -> Result<usize, &'static str>
+where
+    I: Iterator<Item = String>,
+{
+    match args.peek() {
+        Some(next) if !next.starts_with('-') => {
+            args.next().unwrap().parse().map_err(|_| "invalid context count")
+        }
+        _ => Err("missing context count"),
+    }
+}
+
 #[derive(Debug)]
 pub struct Config {
     pub query: String,
-    pub filename: String,
+    pub paths: Vec<String>,
     pub case_sensitive: bool,
+    pub regex: bool,
+    pub line_numbers: bool,
+    pub count: bool,
+    pub invert: bool,
+    pub color: ColorMode,
+    pub before_context: usize,
+    pub after_context: usize,
 }
 
 impl Config {
-    pub fn new<T>(mut args: T) -> Result<Config, &'static str>
+    pub fn new<T>(args: T) -> Result<Config, &'static str>
     where
         T: Iterator<Item = String>,
     {
+        let mut args = args.peekable();
         args.next(); // Skip app name
 
-        let Some(query) = args.next() else {
+        // Split the remaining args into flags (anything starting with '-')
+        // and positional args, the way a real grep's CLI parser would.
+        // -A/-B/-C are special-cased because they each consume the token
+        // that follows them as a line count rather than being a bare flag.
+        let mut options = HashSet::new();
+        let mut positional = Vec::new();
+        let mut after_context = 0;
+        let mut before_context = 0;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-A" | "--after-context" => {
+                    after_context = parse_context_count(&mut args)?;
+                }
+                "-B" | "--before-context" => {
+                    before_context = parse_context_count(&mut args)?;
+                }
+                "-C" | "--context" => {
+                    let n = parse_context_count(&mut args)?;
+                    after_context = n;
+                    before_context = n;
+                }
+                _ if arg.starts_with('-') => {
+                    options.insert(arg);
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+
+        let Some(query) = positional.next() else {
             return Err("missing query");
         };
-        let Some(filename) = args.next() else {
+        let paths: Vec<String> = positional.collect();
+        if paths.is_empty() {
             return Err("missing filename");
-        };
+        }
 
-        // Case sensitive if the env var isn't defined.
-        let case_sensitive = env::var("CASE_INSENSITIVE").is_err();
+        let ignore_case = options.contains("-i") || options.contains("--ignore-case");
+        let regex = options.contains("-e") || options.contains("--regex");
+        let line_numbers = options.contains("-n");
+        let count = options.contains("-c");
+        let invert = options.contains("-v");
+
+        let color = options
+            .iter()
+            .find_map(|opt| opt.strip_prefix("--color=").and_then(ColorMode::parse))
+            .unwrap_or(ColorMode::Auto);
+
+        // Fall back to the env var if no flag was given, so existing
+        // workflows that set CASE_INSENSITIVE keep working.
+        let case_sensitive = !ignore_case && env::var("CASE_INSENSITIVE").is_err();
 
         Ok(Config {
             query,
-            filename,
+            paths,
             case_sensitive,
+            regex,
+            line_numbers,
+            count,
+            invert,
+            color,
+            before_context,
+            after_context,
         })
     }
 }
 
+fn build_matcher(config: &Config) -> Result<Box<dyn Matcher>, Box<dyn Error>> {
+    if config.regex {
+        Ok(Box::new(RegexMatcher::new(
+            &config.query,
+            !config.case_sensitive,
+        )?))
+    } else if config.case_sensitive {
+        Ok(Box::new(LiteralMatcher::new(&config.query)))
+    } else {
+        Ok(Box::new(CaseInsensitiveMatcher::new(&config.query)))
+    }
+}
+
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(&config.filename)?;
+    let matcher = build_matcher(&config)?;
 
-    let results = if config.case_sensitive {
-        search(&config.query, &contents)
-    } else {
-        search_case_insensitive(&config.query, &contents)
-    };
+    let files: Vec<_> = config
+        .paths
+        .iter()
+        .flat_map(|path| collect_files(path))
+        .collect();
+    let multiple_files = files.len() > 1;
+
+    let has_context = config.before_context > 0 || config.after_context > 0;
 
-    for line in results {
-        println!("{}", line);
+    for file in files {
+        let contents = match fs::read_to_string(&file) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("minigrep: skipping '{}': {}", file.display(), e);
+                continue;
+            }
+        };
+
+        if has_context && !config.count {
+            print_with_context(&config, matcher.as_ref(), &file, &contents, multiple_files)?;
+            continue;
+        }
+
+        let results = search(matcher.as_ref(), &contents, config.invert);
+
+        if config.count {
+            if multiple_files {
+                println!("{}:{}", file.display(), results.len());
+            } else {
+                println!("{}", results.len());
+            }
+            continue;
+        }
+
+        for (index, line) in results {
+            let mut prefix = String::new();
+            if multiple_files {
+                write!(prefix, "{}:", file.display())?;
+            }
+            if config.line_numbers {
+                write!(prefix, "{}:", index + 1)?;
+            }
+            let matches = matcher.find_matches(line);
+            println!("{}{}", prefix, highlight::highlight(line, &matches, config.color));
+        }
     }
 
     Ok(())
 }
 
-pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    contents.lines()
-        .filter(|l| l.contains(query))
-        .collect()
-}
+/// Prints matches together with their surrounding `-A`/`-B`/`-C` context,
+/// grouping adjacent windows and separating distinct groups with `--`.
+fn print_with_context(
+    config: &Config,
+    matcher: &dyn Matcher,
+    file: &std::path::Path,
+    contents: &str,
+    multiple_files: bool,
+) -> Result<(), Box<dyn Error>> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let matched_indices: Vec<usize> = search(matcher, contents, config.invert)
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect();
+    let matched_set: HashSet<usize> = matched_indices.iter().copied().collect();
+
+    let windows = context_windows(
+        &matched_indices,
+        config.before_context,
+        config.after_context,
+        lines.len(),
+    );
 
-pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    let query = query.to_lowercase();
+    for (group_index, (start, end)) in windows.into_iter().enumerate() {
+        if group_index > 0 {
+            println!("--");
+        }
 
-    contents.lines()
-        .filter(|s| s.to_lowercase().contains(&query))
+        for (index, &line) in lines.iter().enumerate().take(end + 1).skip(start) {
+            let mut prefix = String::new();
+            if multiple_files {
+                write!(prefix, "{}:", file.display())?;
+            }
+            if config.line_numbers {
+                write!(prefix, "{}:", index + 1)?;
+            }
+
+            if matched_set.contains(&index) {
+                let matches = matcher.find_matches(line);
+                println!("{}{}", prefix, highlight::highlight(line, &matches, config.color));
+            } else {
+                println!("{}{}", prefix, line);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn search<'a>(matcher: &dyn Matcher, contents: &'a str, invert: bool) -> Vec<(usize, &'a str)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| matcher.is_match(line) != invert)
         .collect()
 }
 
@@ -75,7 +254,54 @@ mod tests {
         assert!(config.is_ok());
         let config = config.unwrap();
         assert_eq!(config.query, "search");
-        assert_eq!(config.filename, "file");
+        assert_eq!(config.paths, vec!["file"]);
+    }
+
+    #[test]
+    fn config_multiple_paths() {
+        let args = ["app", "search", "file1", "file2"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.paths, vec!["file1", "file2"]);
+    }
+
+    #[test]
+    fn config_ignore_case_flag_leading() {
+        let args = ["app", "-i", "search", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.query, "search");
+        assert_eq!(config.paths, vec!["file"]);
+        assert!(!config.case_sensitive);
+    }
+
+    #[test]
+    fn config_ignore_case_flag_trailing() {
+        let args = ["app", "search", "file", "--ignore-case"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert!(!config.case_sensitive);
+    }
+
+    #[test]
+    fn config_ignore_case_flag_between_positionals() {
+        let args = ["app", "search", "-i", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.query, "search");
+        assert_eq!(config.paths, vec!["file"]);
+        assert!(!config.case_sensitive);
+    }
+
+    #[test]
+    fn config_no_flag_is_case_sensitive() {
+        let args = ["app", "search", "file"].iter().map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert!(config.case_sensitive);
     }
 
     #[test]
@@ -100,32 +326,35 @@ mod tests {
 
     #[test]
     fn one_result() {
-        let query = "duct";
+        let matcher = LiteralMatcher::new("duct");
         let contents = "\
 Rust:
 safe, fast, productive.
 Pick three.";
 
-        assert_eq!(vec!["safe, fast, productive."], search(query, contents));
+        assert_eq!(
+            vec![(1, "safe, fast, productive.")],
+            search(&matcher, contents, false)
+        );
     }
 
     #[test]
     fn multiple_results() {
-        let query = "u";
+        let matcher = LiteralMatcher::new("u");
         let contents = "\
 Rust:
 safe, fast, productive.
 Pick three.";
 
         assert_eq!(
-            vec!["Rust:", "safe, fast, productive."],
-            search(query, contents)
+            vec![(0, "Rust:"), (1, "safe, fast, productive.")],
+            search(&matcher, contents, false)
         );
     }
 
     #[test]
     fn case_insensitive() {
-        let query = "RuSt";
+        let matcher = CaseInsensitiveMatcher::new("RuSt");
         let contents = "\
 Rust:
 safe, fast, productive.
@@ -133,8 +362,170 @@ Pick three.,
 Trust me.";
 
         assert_eq!(
-            vec!["Rust:", "Trust me."],
-            search_case_insensitive(query, contents)
+            vec![(0, "Rust:"), (3, "Trust me.")],
+            search(&matcher, contents, false)
+        );
+    }
+
+    #[test]
+    fn case_insensitive_handles_case_folding_length_changes() {
+        // 'İ' (U+0130) is 2 bytes but lowercases to the 3-byte 'i̇', which
+        // used to shift match offsets off this line's char boundaries.
+        let matcher = CaseInsensitiveMatcher::new("stanbul");
+        let contents = "İstanbul is great";
+
+        assert_eq!(matcher.find_matches(contents), vec![(2, 9)]);
+        assert_eq!(
+            highlight::highlight(contents, &matcher.find_matches(contents), ColorMode::Never),
+            contents
         );
     }
+
+    #[test]
+    fn regex_match() {
+        let matcher = RegexMatcher::new(r"R\w+:", false).unwrap();
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.";
+
+        assert_eq!(vec![(0, "Rust:")], search(&matcher, contents, false));
+    }
+
+    #[test]
+    fn regex_case_insensitive() {
+        let matcher = RegexMatcher::new(r"rust", true).unwrap();
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.";
+
+        assert_eq!(vec![(0, "Rust:")], search(&matcher, contents, false));
+    }
+
+    #[test]
+    fn invert_match() {
+        let matcher = LiteralMatcher::new("duct");
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.";
+
+        assert_eq!(
+            vec![(0, "Rust:"), (2, "Pick three.")],
+            search(&matcher, contents, true)
+        );
+    }
+
+    #[test]
+    fn config_regex_flag() {
+        let args = ["app", "-e", "R.+:", "file"].iter().map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert!(config.regex);
+    }
+
+    #[test]
+    fn config_line_numbers_count_invert_flags() {
+        let args = ["app", "-n", "-c", "-v", "search", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert!(config.line_numbers);
+        assert!(config.count);
+        assert!(config.invert);
+    }
+
+    #[test]
+    fn config_color_flag() {
+        let args = ["app", "--color=always", "search", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.color, ColorMode::Always);
+    }
+
+    #[test]
+    fn config_defaults_to_auto_color() {
+        let args = ["app", "search", "file"].iter().map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.color, ColorMode::Auto);
+    }
+
+    #[test]
+    fn config_after_and_before_context_flags() {
+        let args = ["app", "-A", "2", "-B", "1", "search", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.after_context, 2);
+        assert_eq!(config.before_context, 1);
+    }
+
+    #[test]
+    fn config_context_flag_sets_both() {
+        let args = ["app", "-C", "3", "search", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args).unwrap();
+        assert_eq!(config.after_context, 3);
+        assert_eq!(config.before_context, 3);
+    }
+
+    #[test]
+    fn config_context_flag_followed_by_another_flag_is_an_error() {
+        let args = ["app", "-A", "-B", "2", "query", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args);
+        let Err(e) = config else {
+            panic!("expected an error, got {:?}", config);
+        };
+        assert_eq!(e, "missing context count");
+    }
+
+    #[test]
+    fn config_context_flag_at_end_of_args_is_an_error() {
+        let args = ["app", "query", "file", "-A"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args);
+        let Err(e) = config else {
+            panic!("expected an error, got {:?}", config);
+        };
+        assert_eq!(e, "missing context count");
+    }
+
+    #[test]
+    fn config_context_flag_with_non_numeric_count_is_an_error() {
+        let args = ["app", "-A", "nope", "query", "file"]
+            .iter()
+            .map(|s| s.to_string());
+        let config = Config::new(args);
+        let Err(e) = config else {
+            panic!("expected an error, got {:?}", config);
+        };
+        assert_eq!(e, "invalid context count");
+    }
+
+    #[test]
+    fn invert_with_context_shows_context_around_non_matching_lines() {
+        let matcher = LiteralMatcher::new("duct");
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+The end.";
+
+        // With invert, "hits" are the non-matching lines: 0 ("Rust:"),
+        // 2 ("Pick three.") and 3 ("The end."), not line 1.
+        let matched_indices: Vec<usize> = search(&matcher, contents, true)
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+
+        assert_eq!(matched_indices, vec![0, 2, 3]);
+
+        // One line of after-context around those hits pulls in line 1.
+        assert_eq!(context_windows(&matched_indices, 0, 1, 4), vec![(0, 3)]);
+    }
 }
@@ -0,0 +1,103 @@
+use regex::Regex;
+
+/// Something that can decide whether a query matches a line of text.
+///
+/// `search` takes a `&dyn Matcher` so it doesn't need to know whether
+/// it's doing a plain substring search or a compiled regex search.
+pub trait Matcher {
+    /// Returns the byte ranges of every match in `line`.
+    fn find_matches(&self, line: &str) -> Vec<(usize, usize)>;
+
+    fn is_match(&self, line: &str) -> bool {
+        !self.find_matches(line).is_empty()
+    }
+}
+
+/// Matches lines containing `query` as a literal substring.
+pub struct LiteralMatcher {
+    query: String,
+}
+
+impl LiteralMatcher {
+    pub fn new(query: &str) -> Self {
+        LiteralMatcher {
+            query: query.to_string(),
+        }
+    }
+}
+
+impl Matcher for LiteralMatcher {
+    fn find_matches(&self, line: &str) -> Vec<(usize, usize)> {
+        find_literal_matches(line, &self.query)
+    }
+}
+
+/// Matches lines containing `query` as a case-insensitive literal substring.
+///
+/// Built on a case-insensitive regex over the escaped, literal query rather
+/// than lowercasing `line` ourselves: `str::to_lowercase` can change a
+/// string's byte length (e.g. `İ` U+0130 is 2 bytes but lowercases to the
+/// 3-byte `i̇`), which would shift match offsets off the original line's
+/// char boundaries. The regex engine computes offsets against `line` as-is.
+pub struct CaseInsensitiveMatcher {
+    regex: Regex,
+}
+
+impl CaseInsensitiveMatcher {
+    pub fn new(query: &str) -> Self {
+        let pattern = format!("(?i){}", regex::escape(query));
+        CaseInsensitiveMatcher {
+            regex: Regex::new(&pattern).expect("escaped literal query is always a valid regex"),
+        }
+    }
+}
+
+impl Matcher for CaseInsensitiveMatcher {
+    fn find_matches(&self, line: &str) -> Vec<(usize, usize)> {
+        self.regex.find_iter(line).map(|m| (m.start(), m.end())).collect()
+    }
+}
+
+/// Matches lines against a compiled regex, built once from `query`.
+pub struct RegexMatcher {
+    regex: Regex,
+}
+
+impl RegexMatcher {
+    pub fn new(query: &str, case_insensitive: bool) -> Result<Self, regex::Error> {
+        let pattern = if case_insensitive {
+            format!("(?i){}", query)
+        } else {
+            query.to_string()
+        };
+
+        Ok(RegexMatcher {
+            regex: Regex::new(&pattern)?,
+        })
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn find_matches(&self, line: &str) -> Vec<(usize, usize)> {
+        self.regex.find_iter(line).map(|m| (m.start(), m.end())).collect()
+    }
+}
+
+/// Finds non-overlapping byte ranges of `query` within `line`.
+fn find_literal_matches(line: &str, query: &str) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while let Some(offset) = line[start..].find(query) {
+        let match_start = start + offset;
+        let match_end = match_start + query.len();
+        matches.push((match_start, match_end));
+        start = match_end;
+    }
+
+    matches
+}